@@ -0,0 +1,190 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+
+use crate::{
+    food::Food,
+    movement::Velocity,
+    spatial_grid::SpatialGrid,
+    steering_agent::spawn_agent,
+    GameRng,
+};
+
+pub struct EvolutionPlugin;
+
+impl Plugin for EvolutionPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, (metabolism, eat_food, reproduction, starvation).chain());
+    }
+}
+
+/// Standard deviation of the Gaussian mutation, expressed as a fraction of each
+/// gene's own magnitude (`gene += N(0, MUTATION_SIGMA * gene)`).
+const MUTATION_SIGMA: f32 = 0.1;
+
+/// Energy burned per unit of distance travelled each tick.
+const METABOLIC_COST: f32 = 0.5;
+
+/// How close an agent has to be to a food entity to consume it.
+const EAT_RADIUS: f32 = 12.0;
+
+/// Heritable traits of an agent. [`crate::steering_agent::SteeringAgentBundle`]
+/// reads its steering components straight out of a genome so that offspring can
+/// inherit (and mutate) their parent's behaviour.
+#[derive(Component, Clone)]
+pub struct Genome {
+    pub max_speed: f32,
+    pub max_force: f32,
+    pub slowing_radius: f32,
+    pub wander_min_radius: f32,
+    pub wander_max_radius: f32,
+    pub alignment_weight: f32,
+    pub cohesion_weight: f32,
+    pub separation_weight: f32,
+}
+
+impl Default for Genome {
+    fn default() -> Self {
+        Self {
+            max_speed: 400.0,
+            max_force: 1000.0,
+            slowing_radius: 50.0,
+            wander_min_radius: 64.0,
+            wander_max_radius: 512.0,
+            alignment_weight: 1.0,
+            cohesion_weight: 1.0,
+            separation_weight: 1.5,
+        }
+    }
+}
+
+impl Genome {
+    /// Produces a mutated copy, nudging every gene by a Gaussian step scaled to
+    /// its magnitude and clamping the result back into a sane range.
+    pub fn mutate(&self, rng: &mut impl Rng) -> Self {
+        let wander_min_radius = mutate_gene(self.wander_min_radius, rng, 8.0, 256.0);
+        let wander_max_radius = mutate_gene(self.wander_max_radius, rng, 64.0, 1024.0).max(wander_min_radius + 1.0);
+
+        Self {
+            max_speed: mutate_gene(self.max_speed, rng, 50.0, 800.0),
+            max_force: mutate_gene(self.max_force, rng, 100.0, 4000.0),
+            slowing_radius: mutate_gene(self.slowing_radius, rng, 10.0, 200.0),
+            wander_min_radius,
+            wander_max_radius,
+            alignment_weight: mutate_gene(self.alignment_weight, rng, 0.0, 4.0),
+            cohesion_weight: mutate_gene(self.cohesion_weight, rng, 0.0, 4.0),
+            separation_weight: mutate_gene(self.separation_weight, rng, 0.0, 4.0),
+        }
+    }
+
+    /// A distinct colour for the agent, derived from its genes so that related
+    /// lineages look alike and divergent ones stand out.
+    pub fn color(&self) -> Color {
+        let hue = (self.max_speed * 0.9 + self.separation_weight * 40.0).rem_euclid(360.0);
+        Color::hsl(hue, 1.0, 0.75)
+    }
+}
+
+fn mutate_gene(value: f32, rng: &mut impl Rng, min: f32, max: f32) -> f32 {
+    let sigma = MUTATION_SIGMA * value.abs();
+
+    if sigma <= f32::EPSILON {
+        return value.clamp(min, max);
+    }
+
+    let normal = Normal::new(0.0, sigma).unwrap();
+
+    (value + normal.sample(rng)).clamp(min, max)
+}
+
+/// An agent's metabolic reserve. It drains with movement and refills by eating;
+/// crossing [`Energy::REPRODUCTION_THRESHOLD`] triggers reproduction and hitting
+/// zero kills the agent.
+#[derive(Component)]
+pub struct Energy(pub f32);
+
+impl Energy {
+    pub const INITIAL: f32 = 50.0;
+    pub const REPRODUCTION_THRESHOLD: f32 = 100.0;
+
+    pub fn new(amount: f32) -> Self {
+        Self(amount)
+    }
+}
+
+fn metabolism(
+    mut query: Query<(&mut Energy, &Velocity)>,
+    time: Res<Time>,
+) {
+    for (mut energy, velocity) in &mut query {
+        energy.0 -= METABOLIC_COST * velocity.0.length() * time.delta_secs();
+    }
+}
+
+fn eat_food(
+    mut commands: Commands,
+    grid: Res<SpatialGrid>,
+    mut agent_query: Query<(&Transform, &mut Energy)>,
+    food_query: Query<&Food>,
+) {
+    let mut eaten = HashSet::new();
+
+    for (transform, mut energy) in &mut agent_query {
+        let position = transform.translation.xy();
+
+        for (entity, _) in grid.query_radius(position, EAT_RADIUS) {
+            if eaten.contains(&entity) {
+                continue;
+            }
+
+            if let Ok(food) = food_query.get(entity) {
+                energy.0 += food.nutritional_value;
+                eaten.insert(entity);
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+fn reproduction(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut query: Query<(&Transform, &mut Energy, &Genome, &Mesh2d)>,
+) {
+    for (transform, mut energy, genome, mesh) in &mut query {
+        if energy.0 < Energy::REPRODUCTION_THRESHOLD {
+            continue;
+        }
+
+        let offspring_energy = energy.0 / 2.0;
+        energy.0 -= offspring_energy;
+
+        let offspring_genome = genome.mutate(&mut rng.0);
+        let material = materials.add(ColorMaterial::from_color(offspring_genome.color()));
+
+        spawn_agent(
+            transform.translation.xy(),
+            offspring_genome,
+            offspring_energy,
+            mesh.0.clone(),
+            material,
+            &mut commands,
+            &mut rng.0,
+        );
+    }
+}
+
+fn starvation(
+    mut commands: Commands,
+    query: Query<(Entity, &Energy)>,
+) {
+    for (entity, energy) in &query {
+        if energy.0 <= 0.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
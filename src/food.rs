@@ -4,7 +4,7 @@ use bevy::{color::palettes::css::GREEN, prelude::*, window::PrimaryWindow};
 use rand::Rng;
 use rand_distr::{Distribution, Exp};
 
-use crate::{movement::Velocity, GameRng};
+use crate::{movement::Velocity, spatial_grid::SpatialGrid, GameRng};
 
 pub struct FoodPlugin;
 
@@ -133,28 +133,28 @@ fn spawn_initial_food(
 }
 
 fn count_nearby_food(
-    positions_query: &Query<&Transform, With<Food>>,
+    grid: &SpatialGrid,
+    food_marker: &Query<(), With<Food>>,
     position: Vec2,
     radius: f32
 ) -> usize {
-    positions_query
-        .iter()
-        .filter(|&transform| {
-            position.distance_squared(transform.translation.xy()) < radius * radius
-        })
+    grid.query_radius(position, radius)
+        .into_iter()
+        .filter(|&(entity, _)| food_marker.get(entity).is_ok())
         .count()
 }
 
 fn food_duplication(
     mut commands: Commands,
     mut rng: ResMut<GameRng>,
+    grid: Res<SpatialGrid>,
     food_query: Query<(&Transform, &Food)>,
-    positions_query: Query<&Transform, With<Food>>,
+    food_marker: Query<(), With<Food>>,
     food_assets: Res<FoodAssets>,
     time: Res<Time>,
 ) {
     for (transform, food) in &food_query {
-        let nearby_food = count_nearby_food(&positions_query, transform.translation.xy(), food.neighbour_radius);
+        let nearby_food = count_nearby_food(&grid, &food_marker, transform.translation.xy(), food.neighbour_radius);
 
         if nearby_food < food.max_neighbours {
             let chance = food.duplication_chance * time.delta_secs();
@@ -184,27 +184,36 @@ fn food_velocity_damping(
 }
 
 fn food_cohesion(
-    mut query: Query<(&Transform, &mut Velocity, &Food)>,
+    grid: Res<SpatialGrid>,
+    mut query: Query<(Entity, &Transform, &mut Velocity, &Food)>,
+    food_marker: Query<(), With<Food>>,
     time: Res<Time>
 ) {
-    let mut food_iter = query.iter_combinations_mut();
+    for (entity, transform, mut velocity, food) in &mut query {
+        let position = transform.translation.xy();
+        let radius = food.cohesion_radius.max(food.seperation_radius);
 
-    while let Some([(transform_a, mut velocity_a, food_a), (transform_b, ..)]) = food_iter.fetch_next() {
-        let delta = (transform_b.translation - transform_a.translation).xy();
-        let distance = delta.length();
+        for (other, other_position) in grid.query_radius(position, radius) {
+            if other == entity || food_marker.get(other).is_err() {
+                continue;
+            }
 
-        if distance < f32::EPSILON {
-            continue;
-        }
+            let delta = other_position - position;
+            let distance = delta.length();
 
-        if distance < food_a.cohesion_radius {
-            let attraction_force = delta.normalize_or_zero() * food_a.cohesion_force * time.delta_secs();
-            velocity_a.0 += attraction_force;
-        }
+            if distance < f32::EPSILON {
+                continue;
+            }
 
-        if distance < food_a.seperation_radius {
-            let repulsion_force = delta.normalize_or_zero() * -food_a.seperation_force * time.delta_secs();
-            velocity_a.0 += repulsion_force / distance.max(1.0);
+            if distance < food.cohesion_radius {
+                let attraction_force = delta.normalize_or_zero() * food.cohesion_force * time.delta_secs();
+                velocity.0 += attraction_force;
+            }
+
+            if distance < food.seperation_radius {
+                let repulsion_force = delta.normalize_or_zero() * -food.seperation_force * time.delta_secs();
+                velocity.0 += repulsion_force / distance.max(1.0);
+            }
         }
     }
 }
\ No newline at end of file
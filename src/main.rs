@@ -1,12 +1,18 @@
 use bevy::prelude::*;
+use evolution::EvolutionPlugin;
 use food::FoodPlugin;
 use movement::MovementPlugin;
+use pheromone::PheromonePlugin;
 use rand::{rngs::StdRng, SeedableRng};
+use spatial_grid::SpatialGridPlugin;
 use steering_agent::SteeringAgentPlugin;
 
 mod movement;
 mod steering_agent;
 mod food;
+mod spatial_grid;
+mod pheromone;
+mod evolution;
 
 #[derive(Resource)]
 pub struct GameRng(StdRng);
@@ -21,9 +27,12 @@ fn main() {
     App::new()
         .add_plugins((
             DefaultPlugins,
+            SpatialGridPlugin,
             MovementPlugin,
             SteeringAgentPlugin,
-            FoodPlugin
+            FoodPlugin,
+            PheromonePlugin,
+            EvolutionPlugin
         ))
         .insert_resource(GameRng::new(42))
         .add_systems(Startup, setup)
@@ -0,0 +1,229 @@
+use bevy::prelude::*;
+
+use crate::{
+    food::Food,
+    movement::{Acceleration, MaxSpeed, Velocity},
+    spatial_grid::SpatialGrid,
+    steering_agent::{seek, MaxForce},
+};
+
+pub struct PheromonePlugin;
+
+impl Plugin for PheromonePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(PheromoneField::new(GRID_WIDTH, GRID_HEIGHT, CELL_SIZE, DECAY_RATE, DEPOSIT_AMOUNT))
+            .add_systems(Update, (
+                pheromone_decay,
+                pheromone_diffusion,
+                update_foraging_state,
+                return_home,
+                follow_pheromone,
+            ).chain());
+    }
+}
+
+const GRID_WIDTH: usize = 128;
+const GRID_HEIGHT: usize = 128;
+const CELL_SIZE: f32 = 32.0;
+const DECAY_RATE: f32 = 0.1;
+const DIFFUSION_RATE: f32 = 0.05;
+const DEPOSIT_AMOUNT: f32 = 4.0;
+
+/// The shared nest every agent returns to and forages outward from.
+const HOME_POSITION: Vec2 = Vec2::ZERO;
+const HOME_RADIUS: f32 = 32.0;
+const FOOD_SENSE_RADIUS: f32 = 24.0;
+
+/// Whether an agent is currently searching for food or carrying it back home.
+/// Returning agents lay down pheromone; seeking agents climb the trail.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum ForagingState {
+    SeekFood,
+    ReturnHome,
+}
+
+/// Fixed-resolution scalar pheromone field laid over the world. Cells are
+/// stored row-major in a flat `Vec<f32>` and addressed through world positions.
+#[derive(Resource)]
+pub struct PheromoneField {
+    width: usize,
+    height: usize,
+    cell_size: f32,
+    decay_rate: f32,
+    deposit_amount: f32,
+    cells: Vec<f32>,
+}
+
+impl PheromoneField {
+    pub fn new(width: usize, height: usize, cell_size: f32, decay_rate: f32, deposit_amount: f32) -> Self {
+        Self {
+            width,
+            height,
+            cell_size,
+            decay_rate,
+            deposit_amount,
+            cells: vec![0.0; width * height],
+        }
+    }
+
+    fn cell_coords(&self, position: Vec2) -> Option<(usize, usize)> {
+        let half_width = self.width as f32 * self.cell_size / 2.0;
+        let half_height = self.height as f32 * self.cell_size / 2.0;
+
+        let x = ((position.x + half_width) / self.cell_size).floor() as i32;
+        let y = ((position.y + half_height) / self.cell_size).floor() as i32;
+
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+
+        Some((x as usize, y as usize))
+    }
+
+    fn concentration_at(&self, x: i32, y: i32) -> f32 {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return 0.0;
+        }
+
+        self.cells[y as usize * self.width + x as usize]
+    }
+
+    pub fn deposit(&mut self, position: Vec2, amount: f32) {
+        if let Some((x, y)) = self.cell_coords(position) {
+            self.cells[y * self.width + x] += amount;
+        }
+    }
+
+    /// Builds a steering direction for a seeking agent by sampling the 8 cells
+    /// around its own, weighting each neighbour offset by its concentration.
+    fn gradient(&self, position: Vec2) -> Vec2 {
+        let Some((cell_x, cell_y)) = self.cell_coords(position) else {
+            return Vec2::ZERO;
+        };
+
+        let mut gradient = Vec2::ZERO;
+
+        for offset_x in -1..=1 {
+            for offset_y in -1..=1 {
+                if offset_x == 0 && offset_y == 0 {
+                    continue;
+                }
+
+                let concentration = self.concentration_at(cell_x as i32 + offset_x, cell_y as i32 + offset_y);
+                gradient += Vec2::new(offset_x as f32, offset_y as f32).normalize_or_zero() * concentration;
+            }
+        }
+
+        gradient
+    }
+}
+
+fn pheromone_decay(
+    mut field: ResMut<PheromoneField>,
+    time: Res<Time>,
+) {
+    let retention = (1.0 - field.decay_rate * time.delta_secs()).max(0.0);
+
+    for cell in &mut field.cells {
+        *cell *= retention;
+    }
+}
+
+fn pheromone_diffusion(
+    mut field: ResMut<PheromoneField>,
+) {
+    let width = field.width as i32;
+    let height = field.height as i32;
+
+    let previous = field.cells.clone();
+    let sample = |x: i32, y: i32| {
+        if x < 0 || y < 0 || x >= width || y >= height {
+            0.0
+        } else {
+            previous[(y * width + x) as usize]
+        }
+    };
+
+    for y in 0..height {
+        for x in 0..width {
+            let index = (y * width + x) as usize;
+
+            let neighbour_average = (sample(x - 1, y)
+                + sample(x + 1, y)
+                + sample(x, y - 1)
+                + sample(x, y + 1))
+                / 4.0;
+
+            field.cells[index] = previous[index] + DIFFUSION_RATE * (neighbour_average - previous[index]);
+        }
+    }
+}
+
+fn update_foraging_state(
+    grid: Res<SpatialGrid>,
+    food_marker: Query<(), With<Food>>,
+    mut agent_query: Query<(&Transform, &mut ForagingState)>,
+) {
+    for (transform, mut state) in &mut agent_query {
+        let position = transform.translation.xy();
+
+        match *state {
+            ForagingState::SeekFood => {
+                let found_food = grid
+                    .query_radius(position, FOOD_SENSE_RADIUS)
+                    .into_iter()
+                    .any(|(entity, _)| food_marker.get(entity).is_ok());
+
+                if found_food {
+                    *state = ForagingState::ReturnHome;
+                }
+            }
+            ForagingState::ReturnHome => {
+                if position.distance(HOME_POSITION) < HOME_RADIUS {
+                    *state = ForagingState::SeekFood;
+                }
+            }
+        }
+    }
+}
+
+fn return_home(
+    mut field: ResMut<PheromoneField>,
+    mut agent_query: Query<(&Transform, &Velocity, &mut Acceleration, &MaxSpeed, &MaxForce, &ForagingState)>,
+) {
+    let deposit_amount = field.deposit_amount;
+
+    for (transform, velocity, mut acceleration, max_speed, max_force, state) in &mut agent_query {
+        if *state != ForagingState::ReturnHome {
+            continue;
+        }
+
+        let position = transform.translation.xy();
+
+        field.deposit(position, deposit_amount);
+
+        acceleration.0 += seek(&position, &velocity.0, &HOME_POSITION, max_speed.0, max_force.0);
+    }
+}
+
+fn follow_pheromone(
+    field: Res<PheromoneField>,
+    mut agent_query: Query<(&Transform, &Velocity, &mut Acceleration, &MaxSpeed, &MaxForce, &ForagingState)>,
+) {
+    for (transform, velocity, mut acceleration, max_speed, max_force, state) in &mut agent_query {
+        if *state != ForagingState::SeekFood {
+            continue;
+        }
+
+        let position = transform.translation.xy();
+        let gradient = field.gradient(position);
+
+        if gradient.length_squared() < f32::EPSILON {
+            continue;
+        }
+
+        let target = position + gradient;
+        acceleration.0 += seek(&position, &velocity.0, &target, max_speed.0, max_force.0);
+    }
+}
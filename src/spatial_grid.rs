@@ -0,0 +1,92 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{food::Food, steering_agent::MaxForce};
+
+pub struct SpatialGridPlugin;
+
+impl Plugin for SpatialGridPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(SpatialGrid::new(CELL_SIZE))
+            .add_systems(PreUpdate, rebuild_spatial_grid);
+    }
+}
+
+/// Cell size of the grid. [`SpatialGrid::query_radius`] scans as many rings of
+/// cells as the query radius spans, so callers may pass a radius larger than a
+/// single cell without missing neighbours.
+const CELL_SIZE: f32 = 128.0;
+
+/// Uniform spatial hash that buckets entities into square cells so neighbour
+/// lookups only touch the handful of entities sharing (or bordering) a cell
+/// instead of scanning the whole world.
+#[derive(Resource)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<(Entity, Vec2)>>,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::default(),
+        }
+    }
+
+    fn cell_of(&self, position: Vec2) -> (i32, i32) {
+        (
+            (position.x / self.cell_size).floor() as i32,
+            (position.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    pub fn clear(&mut self) {
+        for bucket in self.cells.values_mut() {
+            bucket.clear();
+        }
+    }
+
+    pub fn insert(&mut self, entity: Entity, position: Vec2) {
+        self.cells
+            .entry(self.cell_of(position))
+            .or_default()
+            .push((entity, position));
+    }
+
+    /// Returns every bucketed entity within `radius` of `position`, scanning
+    /// the cell containing `position` plus as many surrounding rings of cells
+    /// as the radius spans so no neighbour inside `radius` is missed.
+    pub fn query_radius(&self, position: Vec2, radius: f32) -> Vec<(Entity, Vec2)> {
+        let (cell_x, cell_y) = self.cell_of(position);
+        let radius_squared = radius * radius;
+        let rings = (radius / self.cell_size).ceil() as i32;
+
+        let mut result = Vec::new();
+
+        for offset_x in -rings..=rings {
+            for offset_y in -rings..=rings {
+                if let Some(bucket) = self.cells.get(&(cell_x + offset_x, cell_y + offset_y)) {
+                    for &(entity, entity_position) in bucket {
+                        if position.distance_squared(entity_position) < radius_squared {
+                            result.push((entity, entity_position));
+                        }
+                    }
+                }
+            }
+        }
+
+        result
+    }
+}
+
+fn rebuild_spatial_grid(
+    mut grid: ResMut<SpatialGrid>,
+    query: Query<(Entity, &Transform), Or<(With<Food>, With<MaxForce>)>>,
+) {
+    grid.clear();
+
+    for (entity, transform) in &query {
+        grid.insert(entity, transform.translation.xy());
+    }
+}
@@ -3,15 +3,15 @@ use std::f32::consts::TAU;
 use bevy::{math::{vec2, VectorSpace}, prelude::*, window::PrimaryWindow};
 use rand::Rng;
 
-use crate::{movement::{Acceleration, MaxSpeed, Velocity, VelocityDamping}, GameRng};
+use crate::{evolution::{Energy, Genome}, food::Food, movement::{Acceleration, MaxSpeed, Velocity, VelocityDamping}, pheromone::ForagingState, spatial_grid::SpatialGrid, GameRng};
 
 pub struct SteeringAgentPlugin;
 
 impl Plugin for SteeringAgentPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(Startup, spawn_initial_agents)
-            .add_systems(Update, (follow_mouse, wander).chain());
+            .add_systems(Startup, (spawn_initial_agents, spawn_obstacles))
+            .add_systems(Update, (avoid_obstacles_system, plan, act, pursue_and_evade).chain());
     }
 }
 
@@ -20,8 +20,48 @@ pub struct MaxForce(pub f32);
 #[derive(Component)]
 pub struct SlowingRadius(pub f32);
 
+/// The behaviour an agent is currently pursuing. [`plan`] re-evaluates it from
+/// world state each tick and [`act`] turns it into a steering force, so new
+/// behaviours only need a new variant plus a match arm — no marker-component
+/// plumbing.
+#[derive(Component, Clone, Copy)]
+pub enum AgentGoal {
+    Wander,
+    SeekFood(Entity),
+    Flee(Vec2),
+    Arrive(Vec2),
+    Flock,
+}
+
+#[derive(Component)]
+pub struct Obstacle {
+    pub radius: f32,
+}
+
+/// Makes an agent chase a moving target, predicting its future position.
+///
+/// Currently unused scaffolding: no spawner attaches it yet. It is wired into
+/// [`pursue_and_evade`] ready for a predator/prey split layered on top of the
+/// evolution subsystem.
 #[derive(Component)]
-pub struct FollowMouse;
+pub struct Pursue(pub Entity);
+
+/// Makes an agent flee a moving target, predicting its future position.
+///
+/// Currently unused scaffolding: no spawner attaches it yet. It is wired into
+/// [`pursue_and_evade`] ready for a predator/prey split layered on top of the
+/// evolution subsystem.
+#[derive(Component)]
+pub struct Evade(pub Entity);
+
+#[derive(Component)]
+pub struct Flock {
+    pub neighbour_radius: f32,
+    pub separation_radius: f32,
+    pub alignment_weight: f32,
+    pub cohesion_weight: f32,
+    pub separation_weight: f32,
+}
 
 #[derive(Component)]
 pub struct Wander {
@@ -62,19 +102,21 @@ pub struct SteeringAgentBundle {
     damping: VelocityDamping,
     max_speed: MaxSpeed,
     max_force: MaxForce,
-    slowing_radius: SlowingRadius
+    slowing_radius: SlowingRadius,
+    genome: Genome,
 }
 
 impl SteeringAgentBundle {
-    pub fn new(position: Vec2, max_speed: f32, max_force: f32, slowing_radius: f32, damping: f32) -> Self {
+    pub fn new(position: Vec2, genome: Genome, damping: f32) -> Self {
         Self {
             transform: Transform::from_translation(position.extend(0.1)),
             velocity: Velocity::default(),
             acceleration: Acceleration::default(),
             damping: VelocityDamping(damping),
-            max_speed: MaxSpeed(max_speed),
-            max_force: MaxForce(max_force),
-            slowing_radius: SlowingRadius(slowing_radius),
+            max_speed: MaxSpeed(genome.max_speed),
+            max_force: MaxForce(genome.max_force),
+            slowing_radius: SlowingRadius(genome.slowing_radius),
+            genome,
         }
     }
 }
@@ -105,6 +147,34 @@ pub fn flee(
     steering_force
 }
 
+pub fn pursue(
+    current_pos: &Vec2,
+    current_velocity: &Vec2,
+    target_pos: &Vec2,
+    target_velocity: &Vec2,
+    max_speed: f32,
+    max_force: f32,
+) -> Vec2 {
+    let prediction_time = current_pos.distance(*target_pos) / max_speed;
+    let predicted_pos = target_pos + *target_velocity * prediction_time;
+
+    seek(current_pos, current_velocity, &predicted_pos, max_speed, max_force)
+}
+
+pub fn evade(
+    current_pos: &Vec2,
+    current_velocity: &Vec2,
+    target_pos: &Vec2,
+    target_velocity: &Vec2,
+    max_speed: f32,
+    max_force: f32,
+) -> Vec2 {
+    let prediction_time = current_pos.distance(*target_pos) / max_speed;
+    let predicted_pos = target_pos + *target_velocity * prediction_time;
+
+    flee(current_pos, current_velocity, &predicted_pos, max_speed, max_force)
+}
+
 pub fn arrive(
     current_pos: &Vec2,
     current_velocity: &Vec2,
@@ -132,20 +202,162 @@ pub fn arrive(
     steering_force
 }
 
-fn spawn_agent(
-    position: Vec2,
+/// Base length of the look-ahead "feeler"; the actual feeler is scaled down by
+/// how close the agent is to its top speed.
+const FEELER_LENGTH: f32 = 128.0;
+/// Approximate collision radius of an agent, added to an obstacle's radius when
+/// testing the feeler.
+const AGENT_RADIUS: f32 = 9.0;
+/// Weight applied to the avoidance force so it dominates over wander/seek.
+const AVOIDANCE_WEIGHT: f32 = 4.0;
+
+/// Projects a feeler ahead of the agent along its velocity and, if it would
+/// clip a circular obstacle, returns a lateral force steering away from that
+/// obstacle's centre. `obstacles` are `(centre, radius)` pairs.
+pub fn avoid_obstacles(
+    current_pos: &Vec2,
+    current_velocity: &Vec2,
+    agent_radius: f32,
+    obstacles: &[(Vec2, f32)],
     max_speed: f32,
     max_force: f32,
-    slowing_radius: f32,
-    damping: f32,
+) -> Vec2 {
+    let speed = current_velocity.length();
+
+    if speed < f32::EPSILON {
+        return Vec2::ZERO;
+    }
+
+    let heading = *current_velocity / speed;
+    let feeler_length = FEELER_LENGTH * (speed / max_speed).min(1.0);
+
+    let mut nearest: Option<(f32, Vec2, Vec2)> = None;
+
+    for &(center, radius) in obstacles {
+        let projection = (center - current_pos).dot(heading).clamp(0.0, feeler_length);
+        let closest_point = current_pos + heading * projection;
+
+        if closest_point.distance(center) < radius + agent_radius
+            && nearest.map_or(true, |(nearest_projection, ..)| projection < nearest_projection)
+        {
+            nearest = Some((projection, center, closest_point));
+        }
+    }
+
+    let Some((_, center, closest_point)) = nearest else {
+        return Vec2::ZERO;
+    };
+
+    let mut push = closest_point - center;
+
+    if push.length_squared() < f32::EPSILON {
+        push = Vec2::new(-heading.y, heading.x);
+    }
+
+    let desired_velocity = push.normalize_or_zero() * max_speed;
+
+    let steering_force = (desired_velocity - current_velocity).clamp_length_max(max_force);
+
+    steering_force
+}
+
+pub fn alignment(
+    current_velocity: &Vec2,
+    neighbour_velocities: &[Vec2],
+    max_speed: f32,
+    max_force: f32,
+) -> Vec2 {
+    if neighbour_velocities.is_empty() {
+        return Vec2::ZERO;
+    }
+
+    let average_velocity = neighbour_velocities.iter().sum::<Vec2>() / neighbour_velocities.len() as f32;
+    let desired_velocity = average_velocity.normalize_or_zero() * max_speed;
+
+    let steering_force = (desired_velocity - current_velocity).clamp_length_max(max_force);
+
+    steering_force
+}
+
+pub fn cohesion(
+    current_pos: &Vec2,
+    current_velocity: &Vec2,
+    neighbour_positions: &[Vec2],
+    max_speed: f32,
+    max_force: f32,
+) -> Vec2 {
+    if neighbour_positions.is_empty() {
+        return Vec2::ZERO;
+    }
+
+    let centroid = neighbour_positions.iter().sum::<Vec2>() / neighbour_positions.len() as f32;
+
+    seek(current_pos, current_velocity, &centroid, max_speed, max_force)
+}
+
+pub fn separation(
+    current_pos: &Vec2,
+    current_velocity: &Vec2,
+    neighbour_positions: &[Vec2],
+    max_speed: f32,
+    max_force: f32,
+) -> Vec2 {
+    let mut push = Vec2::ZERO;
+
+    for neighbour_pos in neighbour_positions {
+        let delta = current_pos - neighbour_pos;
+        let distance = delta.length();
+
+        if distance > f32::EPSILON {
+            push += delta.normalize() / distance;
+        }
+    }
+
+    if push.length_squared() < f32::EPSILON {
+        return Vec2::ZERO;
+    }
+
+    let desired_velocity = push.normalize_or_zero() * max_speed;
+
+    let steering_force = (desired_velocity - current_velocity).clamp_length_max(max_force);
+
+    steering_force
+}
+
+/// Flocking neighbourhood radii are shared by every agent; only the three
+/// rule weights are heritable (and live on the [`Genome`]).
+const FLOCK_NEIGHBOUR_RADIUS: f32 = 96.0;
+const FLOCK_SEPARATION_RADIUS: f32 = 32.0;
+
+/// Spawns a fully-formed steering agent from a genome, deriving its steering
+/// components, flocking weights and wander target from the genes.
+pub fn spawn_agent(
+    position: Vec2,
+    genome: Genome,
+    energy: f32,
     mesh: Handle<Mesh>,
     material: Handle<ColorMaterial>,
     commands: &mut Commands,
+    rng: &mut impl Rng,
 ) -> Entity {
+    let wander = Wander::new(position, genome.wander_min_radius, genome.wander_max_radius, rng);
+    let flock = Flock {
+        neighbour_radius: FLOCK_NEIGHBOUR_RADIUS,
+        separation_radius: FLOCK_SEPARATION_RADIUS,
+        alignment_weight: genome.alignment_weight,
+        cohesion_weight: genome.cohesion_weight,
+        separation_weight: genome.separation_weight,
+    };
+
     commands.spawn((
-        SteeringAgentBundle::new(position, max_speed, max_force, slowing_radius, damping),
+        SteeringAgentBundle::new(position, genome, 1.0),
         Mesh2d(mesh),
         MeshMaterial2d(material),
+        wander,
+        flock,
+        AgentGoal::Wander,
+        ForagingState::SeekFood,
+        Energy::new(energy),
     )).id()
 }
 
@@ -162,64 +374,237 @@ fn spawn_initial_agents(
     let triangle_left =  Vec2::new(-triangle_height / 3.0     , -triangle_width / 2.0);
     let triangle_right = Vec2::new(-triangle_height / 3.0     , triangle_width / 2.0);
 
+    let mesh = meshes.add(Triangle2d::new(triangle_top, triangle_left, triangle_right));
+
     let agent_count = 8;
 
     for _ in 0..agent_count {
-        let entity = spawn_agent(
+        spawn_agent(
             Vec2::ZERO,
-            400.,
-            1000.,
-            50.,
-            1.0,
-            meshes.add(Triangle2d::new(triangle_top, triangle_left, triangle_right)),
+            Genome::default(),
+            Energy::INITIAL,
+            mesh.clone(),
             materials.add(Color::hsl(rng.0.random_range(0.0..360.0), 1., 0.75)),
-            &mut commands
+            &mut commands,
+            &mut rng.0,
         );
-
-        commands.get_entity(entity).unwrap().insert(Wander::new(Vec2::ZERO, 64., 512., &mut rng.0));
     }
 }
 
-fn follow_mouse(
-    mut query: Query<(&mut Acceleration, &Velocity, &Transform, &MaxSpeed, &MaxForce, &SlowingRadius), With<FollowMouse>>,
+/// How far an agent can sense food before it will commit to chasing it.
+const FOOD_SENSE_RADIUS: f32 = 256.0;
+/// Cursor distance under which the cursor is treated as a threat to flee.
+/// Between this and [`CURSOR_ATTRACT_RADIUS`] the agent arrives at the cursor
+/// instead. [`plan`] picks the goal from the current distance alone each tick,
+/// so there is no hysteresis at either boundary.
+const CURSOR_FLEE_RADIUS: f32 = 96.0;
+/// Cursor distance under which an agent is drawn towards the cursor instead.
+const CURSOR_ATTRACT_RADIUS: f32 = 512.0;
+
+fn plan(
+    grid: Res<SpatialGrid>,
+    food_marker: Query<(), With<Food>>,
+    agent_marker: Query<(), With<Genome>>,
     camera_query: Query<(&Camera, &GlobalTransform)>,
-    window_query: Query<&Window, With<PrimaryWindow>>
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut agent_query: Query<(Entity, &Transform, &mut AgentGoal), With<Genome>>,
 ) {
-    let (camera, camera_transform) = camera_query.single();
-    let window = window_query.single();
-
-    if let Some(viewport_position) = window.cursor_position() {
-        let world_position = camera.viewport_to_world_2d(camera_transform, viewport_position).unwrap_or(Vec2::ZERO);
-
-        for (mut acceleration, velocity, transform, max_speed, max_force, slowing_radius) in &mut query {
-            acceleration.0 += arrive(
-                &transform.translation.xy(),
-                &velocity.0,
-                &world_position,
-                max_speed.0,
-                max_force.0,
-                slowing_radius.0
-            );
+    let cursor = window_query
+        .single()
+        .cursor_position()
+        .and_then(|viewport_position| {
+            let (camera, camera_transform) = camera_query.single();
+            camera.viewport_to_world_2d(camera_transform, viewport_position).ok()
+        });
+
+    for (entity, transform, mut goal) in &mut agent_query {
+        let position = transform.translation.xy();
+
+        if let Some(cursor) = cursor {
+            let distance = position.distance(cursor);
+
+            if distance < CURSOR_FLEE_RADIUS {
+                *goal = AgentGoal::Flee(cursor);
+                continue;
+            }
+
+            if distance < CURSOR_ATTRACT_RADIUS {
+                *goal = AgentGoal::Arrive(cursor);
+                continue;
+            }
         }
+
+        if let Some(food) = nearest_food(&grid, &food_marker, position) {
+            *goal = AgentGoal::SeekFood(food);
+            continue;
+        }
+
+        let has_neighbours = grid
+            .query_radius(position, FLOCK_NEIGHBOUR_RADIUS)
+            .into_iter()
+            .any(|(other, _)| other != entity && agent_marker.get(other).is_ok());
+
+        *goal = if has_neighbours { AgentGoal::Flock } else { AgentGoal::Wander };
     }
 }
 
-fn wander(
-    mut agent_query: Query<(&mut Acceleration, &mut Wander, &Velocity, &Transform, &MaxSpeed, &MaxForce, &SlowingRadius)>,
+fn nearest_food(
+    grid: &SpatialGrid,
+    food_marker: &Query<(), With<Food>>,
+    position: Vec2,
+) -> Option<Entity> {
+    grid.query_radius(position, FOOD_SENSE_RADIUS)
+        .into_iter()
+        .filter(|&(entity, _)| food_marker.get(entity).is_ok())
+        .min_by(|(_, a), (_, b)| {
+            position.distance_squared(*a).total_cmp(&position.distance_squared(*b))
+        })
+        .map(|(entity, _)| entity)
+}
+
+fn act(
+    grid: Res<SpatialGrid>,
     mut rng: ResMut<GameRng>,
+    transforms: Query<&Transform>,
+    neighbour_velocity_query: Query<&Velocity, With<Flock>>,
+    mut agent_query: Query<(Entity, &AgentGoal, &mut Acceleration, &Velocity, &mut Wander, &Transform, &MaxSpeed, &MaxForce, &SlowingRadius, &Flock)>,
+) {
+    for (entity, goal, mut acceleration, velocity, mut wander, transform, max_speed, max_force, slowing_radius, flock) in &mut agent_query {
+        let position = transform.translation.xy();
+
+        match goal {
+            AgentGoal::Wander => {
+                if position.distance_squared(wander.target) < 1. {
+                    wander.randomize(Vec2::ZERO, &mut rng.0);
+                }
+
+                acceleration.0 += arrive(&position, &velocity.0, &wander.target, max_speed.0, max_force.0, slowing_radius.0);
+            }
+            AgentGoal::SeekFood(target) => {
+                if let Ok(target_transform) = transforms.get(*target) {
+                    acceleration.0 += seek(&position, &velocity.0, &target_transform.translation.xy(), max_speed.0, max_force.0);
+                }
+            }
+            AgentGoal::Flee(point) => {
+                acceleration.0 += flee(&position, &velocity.0, point, max_speed.0, max_force.0);
+            }
+            AgentGoal::Arrive(point) => {
+                acceleration.0 += arrive(&position, &velocity.0, point, max_speed.0, max_force.0, slowing_radius.0);
+            }
+            AgentGoal::Flock => {
+                let mut neighbour_positions = Vec::new();
+                let mut neighbour_velocities = Vec::new();
+                let mut separation_positions = Vec::new();
+
+                for (other, other_position) in grid.query_radius(position, flock.neighbour_radius) {
+                    if other == entity {
+                        continue;
+                    }
+
+                    let Ok(other_velocity) = neighbour_velocity_query.get(other) else {
+                        continue;
+                    };
+
+                    neighbour_positions.push(other_position);
+                    neighbour_velocities.push(other_velocity.0);
+
+                    if position.distance_squared(other_position) < flock.separation_radius * flock.separation_radius {
+                        separation_positions.push(other_position);
+                    }
+                }
+
+                let alignment_force = alignment(&velocity.0, &neighbour_velocities, max_speed.0, max_force.0);
+                let cohesion_force = cohesion(&position, &velocity.0, &neighbour_positions, max_speed.0, max_force.0);
+                let separation_force = separation(&position, &velocity.0, &separation_positions, max_speed.0, max_force.0);
+
+                acceleration.0 += alignment_force * flock.alignment_weight
+                    + cohesion_force * flock.cohesion_weight
+                    + separation_force * flock.separation_weight;
+            }
+        }
+    }
+}
+
+/// Applies [`pursue`]/[`evade`] to any agent carrying a [`Pursue`]/[`Evade`]
+/// component. No spawner attaches those components today, so this is dormant
+/// until a predator/prey model opts agents into it.
+fn pursue_and_evade(
+    mut agent_query: Query<(&mut Acceleration, &Velocity, &Transform, &MaxSpeed, &MaxForce, Option<&Pursue>, Option<&Evade>)>,
+    target_query: Query<(&Transform, &Velocity)>,
 ) {
-    for (mut acceleration, mut wander, velocity, transform, max_speed, max_force, slowing_radius) in &mut agent_query {
-        if transform.translation.xy().distance_squared(wander.target) < 1. {
-            wander.randomize(Vec2::ZERO, &mut rng.0);
+    for (mut acceleration, velocity, transform, max_speed, max_force, pursue_target, evade_target) in &mut agent_query {
+        let position = transform.translation.xy();
+
+        if let Some(Pursue(target)) = pursue_target {
+            if let Ok((target_transform, target_velocity)) = target_query.get(*target) {
+                acceleration.0 += pursue(
+                    &position,
+                    &velocity.0,
+                    &target_transform.translation.xy(),
+                    &target_velocity.0,
+                    max_speed.0,
+                    max_force.0,
+                );
+            }
         }
 
-        acceleration.0 += arrive(
+        if let Some(Evade(target)) = evade_target {
+            if let Ok((target_transform, target_velocity)) = target_query.get(*target) {
+                acceleration.0 += evade(
+                    &position,
+                    &velocity.0,
+                    &target_transform.translation.xy(),
+                    &target_velocity.0,
+                    max_speed.0,
+                    max_force.0,
+                );
+            }
+        }
+    }
+}
+
+fn avoid_obstacles_system(
+    mut agent_query: Query<(&mut Acceleration, &Velocity, &Transform, &MaxSpeed, &MaxForce), With<Genome>>,
+    obstacle_query: Query<(&Transform, &Obstacle)>,
+) {
+    let obstacles: Vec<(Vec2, f32)> = obstacle_query
+        .iter()
+        .map(|(transform, obstacle)| (transform.translation.xy(), obstacle.radius))
+        .collect();
+
+    if obstacles.is_empty() {
+        return;
+    }
+
+    for (mut acceleration, velocity, transform, max_speed, max_force) in &mut agent_query {
+        acceleration.0 += avoid_obstacles(
             &transform.translation.xy(),
             &velocity.0,
-            &wander.target,
+            AGENT_RADIUS,
+            &obstacles,
             max_speed.0,
             max_force.0,
-            slowing_radius.0
-        );
+        ) * AVOIDANCE_WEIGHT;
+    }
+}
+
+fn spawn_obstacles(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let radius = 40.0;
+    let positions = [vec2(-200., 0.), vec2(200., 150.), vec2(0., -200.)];
+
+    let mesh = meshes.add(Circle::new(radius));
+    let material = materials.add(ColorMaterial::from_color(Color::srgb(0.4, 0.4, 0.4)));
+
+    for position in positions {
+        commands.spawn((
+            Obstacle { radius },
+            Mesh2d(mesh.clone()),
+            MeshMaterial2d(material.clone()),
+            Transform::from_translation(position.extend(0.)),
+        ));
     }
 }
\ No newline at end of file